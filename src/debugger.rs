@@ -0,0 +1,85 @@
+//! Interactive debugging support: pausing the fetch-decode-execute loop, single-stepping,
+//! breakpoints on PC addresses, and rendering memory/disassembly views of a selected range.
+
+use std::collections::HashSet;
+
+use crate::instruction::disassemble;
+
+/// Tracks whether emulation is paused and which PC addresses should trigger a pause.
+#[derive(Default)]
+pub(crate) struct Debugger {
+    /// `true` while the fetch-decode-execute loop is paused for inspection.
+    paused: bool,
+    /// PC addresses that force a pause when reached.
+    breakpoints: HashSet<u16>,
+    /// Set by `step` to let a single instruction run through while still paused.
+    step: bool,
+}
+
+impl Debugger {
+    pub(crate) fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Requests that one instruction be allowed to run despite being paused.
+    pub(crate) fn step(&mut self) {
+        self.step = true;
+    }
+
+    pub(crate) fn toggle_breakpoint(&mut self, address: u16) {
+        if !self.breakpoints.remove(&address) {
+            self.breakpoints.insert(address);
+        }
+    }
+
+    /// Returns `true` if the fetch-decode-execute loop should execute the instruction at `pc`,
+    /// consuming a pending single-step request if there was one.
+    pub(crate) fn should_run(&mut self, pc: u16) -> bool {
+        if self.breakpoints.contains(&pc) {
+            self.paused = true;
+        }
+        if !self.paused {
+            return true;
+        }
+        if self.step {
+            self.step = false;
+            return true;
+        }
+        false
+    }
+
+    /// Renders `ram[start..end]` as a hex dump, 16 bytes per line. `end` is clamped to the
+    /// size of `ram`.
+    pub(crate) fn memory_view(ram: &[u8], start: u16, end: u16) -> String {
+        let end = end.min(ram.len() as u16);
+        let start = start.min(end);
+        let mut out = String::new();
+        for (offset, chunk) in ram[start as usize..end as usize].chunks(16).enumerate() {
+            out.push_str(&format!("{:04x}: ", start as usize + offset * 16));
+            for byte in chunk {
+                out.push_str(&format!("{byte:02x} "));
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Renders a live disassembly of `ram[start..end]`, one mnemonic per 2-byte instruction.
+    /// `end` is clamped to the size of `ram`.
+    pub(crate) fn disassembly_view(ram: &[u8], start: u16, end: u16) -> String {
+        let end = end.min(ram.len() as u16);
+        let start = start.min(end);
+        let mut out = String::new();
+        let mut address = start;
+        while address + 1 < end {
+            let opcode = (ram[address as usize] as u16) << 8 | ram[address as usize + 1] as u16;
+            out.push_str(&format!("{address:04x}: {}\n", disassemble(opcode)));
+            address += 2;
+        }
+        out
+    }
+}
@@ -0,0 +1,34 @@
+//! Errors that can occur while loading a ROM or stepping the CPU, as an alternative to
+//! panicking or silently ignoring the problem.
+
+use std::fmt;
+
+/// Errors surfaced by `Emulator::load_rom`, `Emulator::execute`, and the main loop's
+/// fetch/decode step.
+#[derive(Debug)]
+pub(crate) enum CpuError {
+    /// The fetched opcode didn't match any known CHIP-8 instruction.
+    UnknownOpcode(u16),
+    /// A `CALL` was made with all 16 levels of the call stack already in use.
+    StackOverflow,
+    /// A `RET` was made with an empty call stack.
+    StackUnderflow,
+    /// The loaded ROM doesn't fit in the memory available after `0x200`.
+    RomTooLarge,
+    /// An instruction addressed memory outside of the 4KB RAM.
+    AddressOutOfBounds,
+}
+
+impl fmt::Display for CpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CpuError::UnknownOpcode(opcode) => write!(f, "unknown opcode {opcode:04x}"),
+            CpuError::StackOverflow => write!(f, "call stack overflow"),
+            CpuError::StackUnderflow => write!(f, "call stack underflow"),
+            CpuError::RomTooLarge => write!(f, "ROM too large to fit in memory"),
+            CpuError::AddressOutOfBounds => write!(f, "address out of bounds"),
+        }
+    }
+}
+
+impl std::error::Error for CpuError {}
@@ -0,0 +1,176 @@
+//! Decoding of raw CHIP-8 opcodes into a typed [`Instruction`], kept separate from
+//! `Emulator::execute` so opcodes can be decoded and tested without any emulator state.
+
+/// Splits a 16-bit opcode into its four nibbles `(a, b, c, d)`, most significant first.
+pub(crate) fn get_nibs(opcode: u16) -> (u8, u8, u8, u8) {
+    (
+        ((opcode & 0xF000) >> 12) as u8,
+        ((opcode & 0x0F00) >> 8) as u8,
+        ((opcode & 0x00F0) >> 4) as u8,
+        (opcode & 0x000F) as u8,
+    )
+}
+
+/// A decoded CHIP-8 instruction, independent of any particular `Emulator` state.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Instruction {
+    ClearDisplay,
+    Return,
+    Jump { nnn: u16 },
+    Call { nnn: u16 },
+    SkipEq { x: u8, kk: u8 },
+    SkipNotEq { x: u8, kk: u8 },
+    SkipEqReg { x: u8, y: u8 },
+    LoadImm { x: u8, kk: u8 },
+    AddImm { x: u8, kk: u8 },
+    LoadReg { x: u8, y: u8 },
+    Or { x: u8, y: u8 },
+    And { x: u8, y: u8 },
+    Xor { x: u8, y: u8 },
+    AddReg { x: u8, y: u8 },
+    SubReg { x: u8, y: u8 },
+    Shr { x: u8, y: u8 },
+    SubnReg { x: u8, y: u8 },
+    Shl { x: u8, y: u8 },
+    SkipNotEqReg { x: u8, y: u8 },
+    SetIndex { nnn: u16 },
+    JumpV0 { nnn: u16 },
+    Random { x: u8, kk: u8 },
+    Draw { x: u8, y: u8, n: u8 },
+    SkipKeyPressed { x: u8 },
+    SkipKeyNotPressed { x: u8 },
+    LoadDt { x: u8 },
+    WaitKey { x: u8 },
+    SetDt { x: u8 },
+    SetSt { x: u8 },
+    AddIndex { x: u8 },
+    LoadSprite { x: u8 },
+    StoreBcd { x: u8 },
+    StoreRegs { x: u8 },
+    LoadRegs { x: u8 },
+}
+
+/// Decodes a raw opcode into an [`Instruction`]. Returns the opcode unchanged as `Err` if it
+/// doesn't match any known CHIP-8 instruction.
+pub(crate) fn decode(opcode: u16) -> Result<Instruction, u16> {
+    let (a, b, c, d) = get_nibs(opcode);
+    let x = b;
+    let y = c;
+    let kk = (opcode & 0x00FF) as u8;
+    let nnn = opcode & 0x0FFF;
+
+    Ok(match (a, b, c, d) {
+        (0x0, 0x0, 0xE, 0x0) => Instruction::ClearDisplay,
+        (0x0, 0x0, 0xE, 0xE) => Instruction::Return,
+        (0x1, ..) => Instruction::Jump { nnn },
+        (0x2, ..) => Instruction::Call { nnn },
+        (0x3, ..) => Instruction::SkipEq { x, kk },
+        (0x4, ..) => Instruction::SkipNotEq { x, kk },
+        (0x5, _, _, 0x0) => Instruction::SkipEqReg { x, y },
+        (0x6, ..) => Instruction::LoadImm { x, kk },
+        (0x7, ..) => Instruction::AddImm { x, kk },
+        (0x8, _, _, 0x0) => Instruction::LoadReg { x, y },
+        (0x8, _, _, 0x1) => Instruction::Or { x, y },
+        (0x8, _, _, 0x2) => Instruction::And { x, y },
+        (0x8, _, _, 0x3) => Instruction::Xor { x, y },
+        (0x8, _, _, 0x4) => Instruction::AddReg { x, y },
+        (0x8, _, _, 0x5) => Instruction::SubReg { x, y },
+        (0x8, _, _, 0x6) => Instruction::Shr { x, y },
+        (0x8, _, _, 0x7) => Instruction::SubnReg { x, y },
+        (0x8, _, _, 0xE) => Instruction::Shl { x, y },
+        (0x9, _, _, 0x0) => Instruction::SkipNotEqReg { x, y },
+        (0xA, ..) => Instruction::SetIndex { nnn },
+        (0xB, ..) => Instruction::JumpV0 { nnn },
+        (0xC, ..) => Instruction::Random { x, kk },
+        (0xD, .., n) => Instruction::Draw { x, y, n },
+        (0xE, _, 0x9, 0xE) => Instruction::SkipKeyPressed { x },
+        (0xE, _, 0xA, 0x1) => Instruction::SkipKeyNotPressed { x },
+        (0xF, _, 0x0, 0x7) => Instruction::LoadDt { x },
+        (0xF, _, 0x0, 0xA) => Instruction::WaitKey { x },
+        (0xF, _, 0x1, 0x5) => Instruction::SetDt { x },
+        (0xF, _, 0x1, 0x8) => Instruction::SetSt { x },
+        (0xF, _, 0x1, 0xE) => Instruction::AddIndex { x },
+        (0xF, _, 0x2, 0x9) => Instruction::LoadSprite { x },
+        (0xF, _, 0x3, 0x3) => Instruction::StoreBcd { x },
+        (0xF, _, 0x5, 0x5) => Instruction::StoreRegs { x },
+        (0xF, _, 0x6, 0x5) => Instruction::LoadRegs { x },
+        _ => return Err(opcode),
+    })
+}
+
+/// Renders an opcode as a mnemonic string (e.g. `LD V2, 0x0A`, `DRW V0, V1, 5`), for the
+/// debugger's disassembly view. Unknown opcodes render as `DW 0x1234`, mirroring how an
+/// assembler emits a raw data word it can't decode.
+pub(crate) fn disassemble(opcode: u16) -> String {
+    match decode(opcode) {
+        Ok(Instruction::ClearDisplay) => "CLS".to_string(),
+        Ok(Instruction::Return) => "RET".to_string(),
+        Ok(Instruction::Jump { nnn }) => format!("JP 0x{nnn:03X}"),
+        Ok(Instruction::Call { nnn }) => format!("CALL 0x{nnn:03X}"),
+        Ok(Instruction::SkipEq { x, kk }) => format!("SE V{x:X}, 0x{kk:02X}"),
+        Ok(Instruction::SkipNotEq { x, kk }) => format!("SNE V{x:X}, 0x{kk:02X}"),
+        Ok(Instruction::SkipEqReg { x, y }) => format!("SE V{x:X}, V{y:X}"),
+        Ok(Instruction::LoadImm { x, kk }) => format!("LD V{x:X}, 0x{kk:02X}"),
+        Ok(Instruction::AddImm { x, kk }) => format!("ADD V{x:X}, 0x{kk:02X}"),
+        Ok(Instruction::LoadReg { x, y }) => format!("LD V{x:X}, V{y:X}"),
+        Ok(Instruction::Or { x, y }) => format!("OR V{x:X}, V{y:X}"),
+        Ok(Instruction::And { x, y }) => format!("AND V{x:X}, V{y:X}"),
+        Ok(Instruction::Xor { x, y }) => format!("XOR V{x:X}, V{y:X}"),
+        Ok(Instruction::AddReg { x, y }) => format!("ADD V{x:X}, V{y:X}"),
+        Ok(Instruction::SubReg { x, y }) => format!("SUB V{x:X}, V{y:X}"),
+        Ok(Instruction::Shr { x, y }) => format!("SHR V{x:X}, V{y:X}"),
+        Ok(Instruction::SubnReg { x, y }) => format!("SUBN V{x:X}, V{y:X}"),
+        Ok(Instruction::Shl { x, y }) => format!("SHL V{x:X}, V{y:X}"),
+        Ok(Instruction::SkipNotEqReg { x, y }) => format!("SNE V{x:X}, V{y:X}"),
+        Ok(Instruction::SetIndex { nnn }) => format!("LD I, 0x{nnn:03X}"),
+        Ok(Instruction::JumpV0 { nnn }) => format!("JP V0, 0x{nnn:03X}"),
+        Ok(Instruction::Random { x, kk }) => format!("RND V{x:X}, 0x{kk:02X}"),
+        Ok(Instruction::Draw { x, y, n }) => format!("DRW V{x:X}, V{y:X}, {n}"),
+        Ok(Instruction::SkipKeyPressed { x }) => format!("SKP V{x:X}"),
+        Ok(Instruction::SkipKeyNotPressed { x }) => format!("SKNP V{x:X}"),
+        Ok(Instruction::LoadDt { x }) => format!("LD V{x:X}, DT"),
+        Ok(Instruction::WaitKey { x }) => format!("LD V{x:X}, K"),
+        Ok(Instruction::SetDt { x }) => format!("LD DT, V{x:X}"),
+        Ok(Instruction::SetSt { x }) => format!("LD ST, V{x:X}"),
+        Ok(Instruction::AddIndex { x }) => format!("ADD I, V{x:X}"),
+        Ok(Instruction::LoadSprite { x }) => format!("LD F, V{x:X}"),
+        Ok(Instruction::StoreBcd { x }) => format!("LD B, V{x:X}"),
+        Ok(Instruction::StoreRegs { x }) => format!("LD [I], V0..V{x:X}"),
+        Ok(Instruction::LoadRegs { x }) => format!("LD V0..V{x:X}, [I]"),
+        Err(raw) => format!("DW 0x{raw:04X}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_nibbles() {
+        assert_eq!(get_nibs(0x1234), (0x1, 0x2, 0x3, 0x4));
+    }
+
+    #[test]
+    fn decodes_known_opcodes() {
+        assert_eq!(decode(0x00E0), Ok(Instruction::ClearDisplay));
+        assert_eq!(decode(0x00EE), Ok(Instruction::Return));
+        assert_eq!(decode(0x6A12), Ok(Instruction::LoadImm { x: 0xA, kk: 0x12 }));
+        assert_eq!(decode(0x1234), Ok(Instruction::Jump { nnn: 0x234 }));
+        assert_eq!(decode(0xD123), Ok(Instruction::Draw { x: 1, y: 2, n: 3 }));
+        assert_eq!(decode(0xF129), Ok(Instruction::LoadSprite { x: 1 }));
+        assert_eq!(decode(0xF155), Ok(Instruction::StoreRegs { x: 1 }));
+    }
+
+    #[test]
+    fn unknown_opcode_is_rejected() {
+        assert_eq!(decode(0x5001), Err(0x5001));
+    }
+
+    #[test]
+    fn disassembles_known_and_unknown_opcodes() {
+        assert_eq!(disassemble(0x00E0), "CLS");
+        assert_eq!(disassemble(0x6A12), "LD VA, 0x12");
+        assert_eq!(disassemble(0xD123), "DRW V1, V2, 3");
+        assert_eq!(disassemble(0x5001), "DW 0x5001");
+    }
+}
@@ -3,9 +3,19 @@ use std::{
     time::{Duration, Instant},
 };
 
-use minifb::{Key, Window, WindowOptions};
+use minifb::{Key, KeyRepeat, Window, WindowOptions};
 use rand::Rng;
+use rodio::{source::Source, OutputStream, Sink};
 use tracing::{info, warn};
+
+mod debugger;
+mod error;
+mod instruction;
+mod quirks;
+use debugger::Debugger;
+use error::CpuError;
+use instruction::{decode, Instruction};
+use quirks::Quirks;
 const WIDTH: usize = 64;
 const HEIGHT: usize = 32;
 
@@ -30,6 +40,16 @@ struct Emulator {
     display: [u8; (WIDTH / 8) * HEIGHT],
     /// Delay timer
     register_dt: u8,
+    /// Sound timer (ST); a tone plays through `audio_sink` while this is nonzero.
+    register_st: u8,
+    /// Audio output stream backing `audio_sink`; kept alive for as long as the emulator runs.
+    _audio_stream: Option<OutputStream>,
+    /// Sink driving the beep tone, started by `init_audio`.
+    audio_sink: Option<Sink>,
+    /// Pause/step/breakpoint state for the interactive debugger.
+    debugger: Debugger,
+    /// Compatibility profile controlling ambiguous CHIP-8 vs SUPER-CHIP instruction semantics.
+    quirks: Quirks,
 }
 
 /// The `Sprite` struct represent a sprite
@@ -41,10 +61,58 @@ struct Sprite {
     content: Vec<u8>,
 }
 
+/// A square wave audio source used to drive the sound timer beep.
+struct SquareWave {
+    frequency: f32,
+    sample_rate: u32,
+    sample_clock: f32,
+}
+
+impl SquareWave {
+    fn new(frequency: f32, sample_rate: u32) -> Self {
+        Self {
+            frequency,
+            sample_rate,
+            sample_clock: 0.0,
+        }
+    }
+}
+
+impl Iterator for SquareWave {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        self.sample_clock = (self.sample_clock + 1.0) % self.sample_rate as f32;
+        let phase = self.sample_clock * self.frequency / self.sample_rate as f32;
+        Some(if phase % 1.0 < 0.5 { 0.2 } else { -0.2 })
+    }
+}
+
+impl Source for SquareWave {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
 impl Emulator {
     /// Loads a ROM file into memory starting at address `0x200`.
     fn load_rom(&mut self, filename: &str) -> Result<(), anyhow::Error> {
         let rom_data = std::fs::read(filename)?;
+        if rom_data.len() > self.ram.len() - 0x200 {
+            return Err(CpuError::RomTooLarge.into());
+        }
         self.load_font_sprites();
         for (i, &byte) in rom_data.iter().enumerate() {
             self.ram[0x200 + i] = byte;
@@ -75,6 +143,17 @@ impl Emulator {
         self.ram[0x050..0x050 + font_sprites.len()].copy_from_slice(&font_sprites);
     }
 
+    /// Starts the audio output and primes (but pauses) the beep sink.
+    fn init_audio(&mut self) -> Result<(), anyhow::Error> {
+        let (stream, handle) = OutputStream::try_default()?;
+        let sink = Sink::try_new(&handle)?;
+        sink.append(SquareWave::new(440.0, 44100));
+        sink.pause();
+        self._audio_stream = Some(stream);
+        self.audio_sink = Some(sink);
+        Ok(())
+    }
+
     fn init_window(&mut self) -> Result<(), anyhow::Error> {
         self.window = Some(Window::new(
             "Chip-8 emulator",
@@ -111,12 +190,23 @@ impl Emulator {
     fn load_sprite(&mut self, sprite: Sprite) {
         self.registers[0xF] = 0;
 
+        let start_x = sprite.x as usize % WIDTH;
+        let start_y = sprite.y as usize % HEIGHT;
+
         for y_offset in 0..sprite.height {
             let content_byte: u8 = sprite.content[y_offset as usize];
-            let y = (sprite.y + y_offset) as usize % HEIGHT;
+            let y_raw = start_y + y_offset as usize;
+            if self.quirks.clip_sprites && y_raw >= HEIGHT {
+                continue;
+            }
+            let y = y_raw % HEIGHT;
 
             for x_offset in 0..sprite.width {
-                let x = (sprite.x + x_offset) as usize % WIDTH;
+                let x_raw = start_x + x_offset as usize;
+                if self.quirks.clip_sprites && x_raw >= WIDTH {
+                    continue;
+                }
+                let x = x_raw % WIDTH;
 
                 let byte_index = (x / 8) + y * (WIDTH / 8);
                 let bit_position = 7 - (x % 8);
@@ -134,6 +224,301 @@ impl Emulator {
             }
         }
     }
+
+    /// Dumps the current registers, stack, PC, and SP as a human-readable string.
+    fn dump_state(&self) -> String {
+        let mut out = format!(
+            "PC: {:04x}  SP: {:02x}  I: {:04x}\n",
+            self.pc, self.sp, self.register_i
+        );
+        for (i, value) in self.registers.iter().enumerate() {
+            out.push_str(&format!("V{i:X}: {value:02x}  "));
+        }
+        out.push('\n');
+        out.push_str(&format!("Stack: {:04x?}\n", &self.stack[..self.sp as usize]));
+        out
+    }
+
+    /// Checks that `[start, start + len)` lies within `ram`, without performing the access.
+    fn check_address(&self, start: u16, len: usize) -> Result<(), CpuError> {
+        if start as usize + len > self.ram.len() {
+            Err(CpuError::AddressOutOfBounds)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Executes a decoded instruction. Returns `Ok(true)` if the caller should skip the normal
+    /// post-instruction advance: either the instruction already updated `pc` itself, or it is a
+    /// `WaitKey` with no key down and must run again from the same `pc`.
+    fn execute(&mut self, instruction: Instruction) -> Result<bool, CpuError> {
+        match instruction {
+            Instruction::ClearDisplay => {
+                self.display = [0; (WIDTH / 8) * HEIGHT];
+                info!("Clearing display");
+                Ok(false)
+            }
+            Instruction::Return => {
+                if self.sp == 0 {
+                    return Err(CpuError::StackUnderflow);
+                }
+                self.sp -= 1;
+                let ret = self.stack[self.sp as usize];
+                self.pc = ret;
+                info!("Returning to address {:4x}", ret);
+                Ok(true)
+            }
+            Instruction::Jump { nnn } => {
+                self.pc = nnn;
+                info!("Jumping to {:04x}", nnn);
+                Ok(true)
+            }
+            Instruction::Call { nnn } => {
+                if self.sp as usize >= self.stack.len() {
+                    return Err(CpuError::StackOverflow);
+                }
+                self.stack[self.sp as usize] = self.pc + 2;
+                self.sp += 1;
+                self.pc = nnn;
+                info!("Calling routine at {:4x}", nnn);
+                Ok(true)
+            }
+            Instruction::SkipEq { x, kk } => {
+                if self.registers[x as usize] == kk {
+                    self.pc += 2;
+                }
+                info!(
+                    "Incrementing pc if V{x} ({:02x}) is equal to {:04x} ",
+                    self.registers[x as usize], kk
+                );
+                Ok(false)
+            }
+            Instruction::SkipNotEq { x, kk } => {
+                if self.registers[x as usize] != kk {
+                    self.pc += 2;
+                }
+                info!("SE v{x} {kk}");
+                Ok(false)
+            }
+            Instruction::SkipEqReg { x, y } => {
+                if self.registers[x as usize] == self.registers[y as usize] {
+                    self.pc += 2;
+                }
+                info!("SE V{x}, V{y}");
+                Ok(false)
+            }
+            Instruction::LoadImm { x, kk } => {
+                self.registers[x as usize] = kk;
+                info!("Loading value {:2x} inside V{:x}", kk, x);
+                Ok(false)
+            }
+            Instruction::AddImm { x, kk } => {
+                self.registers[x as usize] = self.registers[x as usize].wrapping_add(kk);
+                info!("loading value {:4x} into V{x}", kk);
+                Ok(false)
+            }
+            Instruction::LoadReg { x, y } => {
+                self.registers[x as usize] = self.registers[y as usize];
+                info!("V{x} = V{y}");
+                Ok(false)
+            }
+            Instruction::Or { x, y } => {
+                self.registers[x as usize] |= self.registers[y as usize];
+                info!("V{x} = V{x} | V{y}");
+                Ok(false)
+            }
+            Instruction::And { x, y } => {
+                self.registers[x as usize] &= self.registers[y as usize];
+                info!("V{x} = V{x} & V{y}");
+                Ok(false)
+            }
+            Instruction::Xor { x, y } => {
+                self.registers[x as usize] ^= self.registers[y as usize];
+                info!("V{x} = V{x} ^ V{y}");
+                Ok(false)
+            }
+            Instruction::AddReg { x, y } => {
+                let (result, overflowed) =
+                    self.registers[x as usize].overflowing_add(self.registers[y as usize]);
+                self.registers[0xF] = overflowed as u8;
+                self.registers[x as usize] = result;
+                info!("V{x} = V{x} + V{y} as overflow in VF");
+                Ok(false)
+            }
+            Instruction::SubReg { x, y } => {
+                let (result, borrowed) =
+                    self.registers[x as usize].overflowing_sub(self.registers[y as usize]);
+                self.registers[0xF] = if borrowed { 0 } else { 1 };
+                self.registers[x as usize] = result;
+                info!("V{x} = V{x} - V{y}, VF = {}", self.registers[0xF]);
+                Ok(false)
+            }
+            Instruction::Shr { x, y } => {
+                let source = if self.quirks.shift_vx_in_place { x } else { y };
+                let value = self.registers[source as usize];
+                self.registers[0xF] = value & 0x1;
+                self.registers[x as usize] = value >> 1;
+                info!("V{x} >>= 1, VF = {}", value & 0x1);
+                Ok(false)
+            }
+            Instruction::SubnReg { x, y } => {
+                let (result, borrowed) =
+                    self.registers[y as usize].overflowing_sub(self.registers[x as usize]);
+                self.registers[0xF] = if borrowed { 0 } else { 1 };
+                self.registers[x as usize] = result;
+                info!("V{x} = V{y} - V{x}, VF = {}", self.registers[0xF]);
+                Ok(false)
+            }
+            Instruction::Shl { x, y } => {
+                let source = if self.quirks.shift_vx_in_place { x } else { y };
+                let value = self.registers[source as usize];
+                self.registers[0xF] = (value >> 7) & 0x1;
+                self.registers[x as usize] = value << 1;
+                info!("V{x} <<= 1, VF = {}", (value >> 7) & 0x1);
+                Ok(false)
+            }
+            Instruction::SkipNotEqReg { x, y } => {
+                if self.registers[x as usize] != self.registers[y as usize] {
+                    self.pc += 2;
+                }
+                info!("SNE V{x}, V{y}");
+                Ok(false)
+            }
+            Instruction::SetIndex { nnn } => {
+                self.register_i = nnn;
+                info!("Loading value {:2x} inside VI", nnn);
+                Ok(false)
+            }
+            Instruction::JumpV0 { nnn } => {
+                let base = if self.quirks.jump_with_vx {
+                    self.registers[((nnn & 0x0F00) >> 8) as usize]
+                } else {
+                    self.registers[0]
+                };
+                self.pc = nnn + base as u16;
+                info!("Jumping to {:04x} + base", nnn);
+                Ok(true)
+            }
+            Instruction::Random { x, kk } => {
+                let random_number: u8 = rand::thread_rng().gen();
+                self.registers[x as usize] = random_number & kk;
+                info!("Adding random value to V{x}");
+                Ok(false)
+            }
+            Instruction::Draw { x, y, n } => {
+                self.check_address(self.register_i, n as usize)?;
+                let x_pos = self.registers[x as usize];
+                let y_pos = self.registers[y as usize];
+                let sprite_content = self.ram
+                    [self.register_i as usize..(self.register_i as usize + n as usize)]
+                    .to_vec();
+
+                self.load_sprite(Sprite {
+                    x: x_pos,
+                    y: y_pos,
+                    width: 8,
+                    height: n,
+                    content: sprite_content,
+                });
+                info!("Loading sprite in pos {x_pos},{y_pos} of height {n}");
+                Ok(false)
+            }
+            Instruction::SkipKeyPressed { x } => {
+                if let Some(window) = &self.window {
+                    if window.is_key_down(u8_to_key(self.registers[x as usize])) {
+                        self.pc += 2;
+                    }
+                    info!("Checking if key is down");
+                }
+                Ok(false)
+            }
+            Instruction::SkipKeyNotPressed { x } => {
+                if let Some(window) = &self.window {
+                    if !window.is_key_down(u8_to_key(self.registers[x as usize])) {
+                        self.pc += 2;
+                    }
+                    info!("Checking if key is up");
+                }
+                Ok(false)
+            }
+            Instruction::LoadDt { x } => {
+                self.registers[x as usize] = self.register_dt;
+                info!("Loading dt into V{x}");
+                Ok(false)
+            }
+            Instruction::WaitKey { x } => {
+                let pressed = self
+                    .window
+                    .as_ref()
+                    .and_then(|window| (0..=0xF_u8).find(|&key| window.is_key_down(u8_to_key(key))));
+                match pressed {
+                    Some(key) => {
+                        self.registers[x as usize] = key;
+                        info!("Storing key {key:x} into V{x}");
+                        Ok(false)
+                    }
+                    None => Ok(true),
+                }
+            }
+            Instruction::SetDt { x } => {
+                self.register_dt = self.registers[x as usize];
+                info!("Loading V{x} into dt");
+                Ok(false)
+            }
+            Instruction::SetSt { x } => {
+                self.register_st = self.registers[x as usize];
+                info!("Loading V{x} into st");
+                Ok(false)
+            }
+            Instruction::AddIndex { x } => {
+                self.register_i = self
+                    .register_i
+                    .wrapping_add(self.registers[x as usize] as u16);
+                info!("Adding V{x} to VI");
+                Ok(false)
+            }
+            Instruction::LoadSprite { x } => {
+                let sprite_value = self.registers[x as usize];
+                self.register_i = 0x50 + (sprite_value as u16 * 5);
+                info!("Loading embedded sprite number {sprite_value}");
+                Ok(false)
+            }
+            Instruction::StoreBcd { x } => {
+                self.check_address(self.register_i, 3)?;
+                let number = self.registers[x as usize];
+                let value_unit = number % 10;
+                let value_tens = (number / 10) % 10;
+                let value_hundreds = (number / 100) % 10;
+                self.ram[self.register_i as usize] = value_hundreds;
+                self.ram[self.register_i as usize + 1] = value_tens;
+                self.ram[self.register_i as usize + 2] = value_unit;
+                info!("Loading into VI[0..3] values {value_hundreds}, {value_tens}, {value_unit}");
+                Ok(false)
+            }
+            Instruction::StoreRegs { x } => {
+                self.check_address(self.register_i, x as usize + 1)?;
+                for i in 0..=x as usize {
+                    self.ram[self.register_i as usize + i] = self.registers[i]
+                }
+                if self.quirks.increment_index_on_load_store {
+                    self.register_i += x as u16 + 1;
+                }
+                info!("Storing registers V0..=V{x} into memory");
+                Ok(false)
+            }
+            Instruction::LoadRegs { x } => {
+                self.check_address(self.register_i, x as usize + 1)?;
+                for i in 0..=x as usize {
+                    self.registers[i] = self.ram[self.register_i as usize + i]
+                }
+                if self.quirks.increment_index_on_load_store {
+                    self.register_i += x as u16 + 1;
+                }
+                info!("Loading {x} values into registers");
+                Ok(false)
+            }
+        }
+    }
 }
 
 impl Default for Emulator {
@@ -148,6 +533,11 @@ impl Default for Emulator {
             window: None,
             display: [0x0; WIDTH / 8 * HEIGHT],
             register_dt: 0,
+            register_st: 0,
+            _audio_stream: None,
+            audio_sink: None,
+            debugger: Debugger::default(),
+            quirks: Quirks::default(),
         }
     }
 }
@@ -178,184 +568,110 @@ fn u8_to_key(key: u8) -> Key {
     }
 }
 
+/// How often the 60 Hz delay/sound timers tick and the display is presented.
+const TIMER_HZ: u32 = 60;
+/// Default CPU clock when no rate is given on the command line, within the usual
+/// 500-700 instructions/second range real CHIP-8 programs expect (9 cycles per 60 Hz frame).
+const DEFAULT_CPU_HZ: u32 = 540;
+
 fn main() -> Result<(), anyhow::Error> {
     tracing_subscriber::fmt()
         .with_max_level(tracing::Level::INFO)
         .init();
-    let mut emulator = Emulator::default();
+    let mut args = std::env::args().skip(1);
+    let quirks = match args.next().as_deref() {
+        Some("chip8") => Quirks::chip8(),
+        Some("schip") => Quirks::super_chip(),
+        _ => Quirks::default(),
+    };
+    let cpu_hz: u32 = args
+        .next()
+        .and_then(|arg| arg.parse().ok())
+        .unwrap_or(DEFAULT_CPU_HZ);
+    let cycles_per_frame = (cpu_hz / TIMER_HZ).max(1);
+
+    let mut emulator = Emulator {
+        quirks,
+        ..Emulator::default()
+    };
     emulator.load_rom("Pong (1 player).ch8")?;
     emulator.init_window()?;
-    let mut last_timer_update = Instant::now();
-    loop {
-        if last_timer_update.elapsed() >= Duration::from_micros(16667) {
-            if emulator.register_dt > 0 {
-                emulator.register_dt -= 1;
-            }
-            last_timer_update = Instant::now();
-        }
-
-        let instruction_high = emulator.ram[emulator.pc as usize];
-        let instruction_low = emulator.ram[(emulator.pc + 1) as usize];
-        let instruction = (instruction_high as u16) << 8 | instruction_low as u16;
-        let x_nibble = instruction_high & 0x0F;
-        let y_nibble = ((instruction_low as u16) & 0xF0) >> 4;
-        let nnn = ((instruction_high as u16 & 0x0F) << 8) | instruction_low as u16;
-        match instruction {
-            0x6000..=0x6FFF => {
-                emulator.registers[x_nibble as usize] = instruction_low;
-                info!(
-                    "Loading value {:2x} inside V{:x}",
-                    instruction_low, x_nibble
-                )
-            }
-            0xA000..=0xAFFF => {
-                emulator.register_i = nnn;
-                info!("Loading value {:2x} inside VI", nnn);
-            }
-            0xD000..=0xDFFF => {
-                let x_registry = instruction_high & 0x0F;
-                let y_registry = instruction_low >> 4;
-                let sprite_height = instruction_low & 0x0F;
-                let x_pos = emulator.registers[x_registry as usize];
-                let y_pos = emulator.registers[y_registry as usize];
-                let sprite_content = emulator.ram[emulator.register_i as usize
-                    ..(emulator.register_i as usize + sprite_height as usize)]
-                    .to_vec();
+    emulator.init_audio()?;
 
-                emulator.load_sprite(Sprite {
-                    x: x_pos,
-                    y: y_pos,
-                    width: 8,
-                    height: sprite_height,
-                    content: sprite_content,
-                });
-                info!("Loading sprite in pos {x_pos},{y_pos} of height {sprite_height}");
-            }
-            0x2000..=0x2FFF => {
-                emulator.stack[emulator.sp as usize] = emulator.pc + 2;
-                emulator.sp += 1;
-                emulator.pc = nnn;
-                info!("Calling routine at {:4x}", nnn)
-            }
-            0x7000..=0x7FFF => {
-                emulator.registers[x_nibble as usize] =
-                    emulator.registers[x_nibble as usize].wrapping_add(instruction_low);
-                info!("loading value {:4x} into V{x_nibble}", instruction_low)
-            }
-            0x00EE => {
-                emulator.sp -= 1;
-                let ret = emulator.stack[emulator.sp as usize];
-                emulator.pc = ret;
-                info!("Returning to address {:4x}", ret);
-                continue;
-            }
-            0xF065..=0xFF65 if instruction & 0xFF == 0x65 => {
-                for i in 0..=x_nibble as usize {
-                    emulator.registers[i] = emulator.ram[emulator.register_i as usize + i]
-                }
-                info!("Loading {x_nibble} values into registers")
-            }
-            0xF033..=0xFF33 if instruction & 0xFF == 0x33 => {
-                let number = emulator.registers[x_nibble as usize];
-                let value_unit = number % 10;
-                let value_tens = (number / 10) % 10;
-                let value_hundreds = (number / 100) % 10;
-                emulator.ram[emulator.register_i as usize] = value_hundreds;
-                emulator.ram[emulator.register_i as usize + 1] = value_tens;
-                emulator.ram[emulator.register_i as usize + 2] = value_unit;
-                info!("Loading into VI[0..3] values {value_hundreds}, {value_tens}, {value_unit}")
-            }
-            0xF029..=0xFF29 if instruction & 0xFF == 0x29 => {
-                let sprite_value = emulator.registers[x_nibble as usize];
-                emulator.register_i = 0x50 + (sprite_value as u16 * 5);
-                info!("Loading embedded sprite number {sprite_value}")
-            }
+    let frame_duration = Duration::from_secs_f64(1.0 / TIMER_HZ as f64);
+    let mut last_frame = Instant::now();
 
-            0xF007..=0xFF07 if instruction & 0xFF == 0x07 => {
-                emulator.registers[x_nibble as usize] = emulator.register_dt;
-                info!("Loading dt into V{x_nibble}")
-            }
-            0xF015..=0xFF15 if instruction & 0xFF == 0x15 => {
-                emulator.register_dt = emulator.registers[x_nibble as usize];
-                info!("Loading V{x_nibble} into dt")
-            }
-            0x3000..=0x3FFF => {
-                if emulator.registers[x_nibble as usize] == instruction_low {
-                    emulator.pc += 2;
+    loop {
+        if let Some(window) = &emulator.window {
+            if window.is_key_pressed(Key::P, KeyRepeat::No) {
+                emulator.debugger.toggle_pause();
+                if emulator.debugger.is_paused() {
+                    println!("{}", emulator.dump_state());
                 }
-                info!(
-                    "Incrementing pc if V{x_nibble} ({:02x}) is equal to {:04x} ",
-                    emulator.registers[x_nibble as usize], instruction_low
-                )
             }
-            0x1000..=0x1FFF => {
-                emulator.pc = nnn;
-                info!("Jumping to {:04x}", nnn);
-                continue;
+            if window.is_key_pressed(Key::B, KeyRepeat::No) {
+                emulator.debugger.toggle_breakpoint(emulator.pc);
+                info!("Toggled breakpoint at {:04x}", emulator.pc);
             }
-            0xC000..=0xCFFF => {
-                let random_number: u8 = rand::thread_rng().gen();
-                emulator.registers[x_nibble as usize] = random_number & instruction_low;
-                info!("Adding random value to V{x_nibble}");
+            if window.is_key_pressed(Key::M, KeyRepeat::No) {
+                println!(
+                    "{}",
+                    Debugger::disassembly_view(
+                        &emulator.ram,
+                        emulator.pc.saturating_sub(4),
+                        emulator.pc.saturating_add(12)
+                    )
+                );
+                println!(
+                    "{}",
+                    Debugger::memory_view(&emulator.ram, emulator.register_i, emulator.register_i.saturating_add(32))
+                );
             }
-            0xE09E..=0xEF9E if instruction & 0xFF == 0x9E => {
-                if let Some(window) = &emulator.window {
-                    if window.is_key_down(u8_to_key(emulator.registers[x_nibble as usize])) {
-                        emulator.pc += 2;
-                    }
-                    info!("Checking if key is down");
-                    continue;
-                }
+            if emulator.debugger.is_paused() && window.is_key_pressed(Key::N, KeyRepeat::No) {
+                emulator.debugger.step();
             }
-            0xE0A1..=0xEFA1 if instruction & 0xFF == 0xA1 => {
-                if let Some(window) = &emulator.window {
-                    if !window.is_key_down(u8_to_key(emulator.registers[x_nibble as usize])) {
-                        emulator.pc += 2;
-                    }
-                    info!("Checking if key is up");
-                    continue;
-                }
-            }
-            0x8002..=0x8FF2 if instruction & 0xF == 0x2 => {
-                emulator.registers[x_nibble as usize] &= emulator.registers[y_nibble as usize];
-                info!("V{x_nibble} = V{x_nibble} & V{y_nibble}")
-            }
-            0x8004..=0x8FF4 if instruction & 0xF == 0x4 => {
-                let (result, overflowed) = emulator.registers[x_nibble as usize]
-                    .overflowing_add(emulator.registers[y_nibble as usize]);
-                emulator.registers[0xF] = overflowed as u8;
-                emulator.registers[x_nibble as usize] = result;
-                info!("V{x_nibble} = V{x_nibble} + V{y_nibble} as overflow in VF")
-            }
-            0x8005..=0x8FF5 if instruction & 0xF == 0x5 => {
-                let (result, borrowed) = emulator.registers[x_nibble as usize]
-                    .overflowing_sub(emulator.registers[y_nibble as usize]);
-                emulator.registers[0xF] = if borrowed { 0 } else { 1 }; 
-                emulator.registers[x_nibble as usize] = result;
-                info!(
-                    "V{x_nibble} = V{x_nibble} - V{y_nibble}, VF = {}",
-                    emulator.registers[0xF]
-                );
+        }
+
+        if last_frame.elapsed() < frame_duration {
+            sleep(Duration::from_millis(1));
+            continue;
+        }
+        last_frame = Instant::now();
+
+        if emulator.register_dt > 0 {
+            emulator.register_dt -= 1;
+        }
+        if emulator.register_st > 0 {
+            emulator.register_st -= 1;
+        }
+        if let Some(sink) = &emulator.audio_sink {
+            if emulator.register_st > 0 {
+                sink.play();
+            } else {
+                sink.pause();
             }
-            0x8002..=0x8FF0 if instruction & 0xF == 0x0 => {
-                emulator.registers[x_nibble as usize] = emulator.registers[y_nibble as usize];
-                info!("V{x_nibble} = V{y_nibble}")
+        }
+
+        for _ in 0..cycles_per_frame {
+            if !emulator.debugger.should_run(emulator.pc) {
+                break;
             }
-            0x4000..=0x4FFF => {
-                if emulator.registers[x_nibble as usize] != instruction_low {
-                    emulator.pc += 2;
-                }
-                info!("SE v{x_nibble} {instruction_low}")
+
+            emulator.check_address(emulator.pc, 2)?;
+            let instruction_high = emulator.ram[emulator.pc as usize];
+            let instruction_low = emulator.ram[(emulator.pc + 1) as usize];
+            let opcode = (instruction_high as u16) << 8 | instruction_low as u16;
+
+            let instruction = decode(opcode).map_err(CpuError::UnknownOpcode)?;
+            if !emulator.execute(instruction)? {
+                emulator.pc += 2;
             }
-            _ => {
-                println!(
-                    "Instruction {:02x}{:02x} not implemented",
-                    instruction_high, instruction_low
-                );
+
+            if emulator.debugger.is_paused() {
+                println!("{}", emulator.dump_state());
             }
-        };
-        sleep(Duration::from_millis(1));
+        }
+
         emulator.write_to_window()?;
-        emulator.pc += 2;
     }
 }
@@ -0,0 +1,50 @@
+//! Compatibility switches for CHIP-8 instructions whose semantics differ between the
+//! original CHIP-8 interpreter and later variants such as SUPER-CHIP.
+
+/// Selects which ambiguous-instruction behavior the emulator follows. The default matches
+/// this emulator's existing (pre-quirks) behavior.
+pub(crate) struct Quirks {
+    /// `8xy6`/`8xyE` shift Vx in place instead of shifting Vy into Vx.
+    pub(crate) shift_vx_in_place: bool,
+    /// `Fx55`/`Fx65` increment I by `x + 1` after copying registers to/from memory.
+    pub(crate) increment_index_on_load_store: bool,
+    /// `Bnnn` jumps to `nnn + Vx` (using the nibble embedded in `nnn`) instead of `nnn + V0`.
+    pub(crate) jump_with_vx: bool,
+    /// Sprites clip at the screen edge instead of wrapping around to the opposite side.
+    pub(crate) clip_sprites: bool,
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Self {
+            shift_vx_in_place: true,
+            increment_index_on_load_store: false,
+            jump_with_vx: false,
+            clip_sprites: false,
+        }
+    }
+}
+
+impl Quirks {
+    /// Original CHIP-8 behavior: `8xy6`/`8xyE` shift Vy into Vx, `Fx55`/`Fx65` increment I,
+    /// `Bnnn` uses V0, and sprites wrap at the screen edge.
+    pub(crate) fn chip8() -> Self {
+        Self {
+            shift_vx_in_place: false,
+            increment_index_on_load_store: true,
+            jump_with_vx: false,
+            clip_sprites: false,
+        }
+    }
+
+    /// SUPER-CHIP behavior: `8xy6`/`8xyE` shift Vx in place, `Fx55`/`Fx65` leave I untouched,
+    /// `Bnnn` uses Vx, and sprites clip at the screen edge.
+    pub(crate) fn super_chip() -> Self {
+        Self {
+            shift_vx_in_place: true,
+            increment_index_on_load_store: false,
+            jump_with_vx: true,
+            clip_sprites: true,
+        }
+    }
+}